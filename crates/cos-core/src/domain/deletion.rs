@@ -8,13 +8,39 @@
 
 use std::marker::PhantomData;
 
+use sha2::{Digest, Sha256};
+
+use super::hypersim;
+use super::invariant::{self, EvalContext, InvariantSet};
+use super::repair::Repair;
+use crate::proof::{BudgetUsed, Decision, ProofArtifact};
+
+/// Version of this typestate machine, folded into every
+/// `execution_boundary_fingerprint` so a proof artifact records which
+/// revision of the kernel judged the run.
+const STATE_MACHINE_VERSION: &str = "deletion-cos-v1";
+
+/// Fingerprint of the plan this run committed to, bound to the version of
+/// the state machine that executed it.
+fn execution_boundary_fingerprint(plan: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(plan.unwrap_or("").as_bytes());
+    hasher.update(STATE_MACHINE_VERSION.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
 /// Type-level state markers (zero-cost).
 /// Used only to prevent invalid transitions at compile time.
 
+#[derive(Debug)]
 pub struct Draft;
+#[derive(Debug)]
 pub struct Validated;
+#[derive(Debug)]
 pub struct Planned;
+#[derive(Debug)]
 pub struct Converged;
+#[derive(Debug)]
 pub struct Executed;
 
 #[derive(Debug)]
@@ -30,11 +56,12 @@ pub struct AccountId(pub String);
 pub struct DeletionCOS<S> {
     pub account: AccountId,
     pub evidence: Option<String>,
-    pub invariants: Option<String>,
+    pub invariants: Option<InvariantSet>,
     pub plan: Option<String>,
     pub report: Option<ConvergenceReport>,
     pub exec: Option<ExecutionResult>,
     pub halt: Option<HaltReason>,
+    pub budget: Option<BudgetUsed>,
     pub _s: PhantomData<S>,
 }
 
@@ -75,6 +102,7 @@ impl DeletionCOS<Draft> {
             report: None,
             exec: None,
             halt: None,
+            budget: None,
             _s: PhantomData,
         }
     }
@@ -85,20 +113,31 @@ impl DeletionCOS<Draft> {
         self
     }
 
-    /// Attach invariants.
-    pub fn attach_invariants(mut self, invariants: String) -> Self {
+    /// Attach the structured, versioned invariant set this run must satisfy.
+    pub fn attach_invariants(mut self, invariants: InvariantSet) -> Self {
         self.invariants = Some(invariants);
         self
     }
 
-    /// Validate and move to Validated state.
-    /// Note: In real implementation, this would check evidence + invariants.
+    /// Validate and move to Validated state: evidence and an invariant set
+    /// must be attached, and the invariant set must actually hold against
+    /// this run's context via the same `invariant::evaluate` that
+    /// `run_hypersim` judges candidate futures with.
     pub fn validate(self) -> Result<DeletionCOS<Validated>, String> {
         if self.evidence.is_none() {
             return Err("Evidence required".into());
         }
-        if self.invariants.is_none() {
+        let Some(invariant_set) = self.invariants.as_ref() else {
             return Err("Invariants required".into());
+        };
+
+        let ctx = EvalContext {
+            evidence_attached: self.evidence.is_some(),
+            ..Default::default()
+        };
+        let eval = invariant::evaluate(invariant_set, &ctx);
+        if !eval.satisfied {
+            return Err(format!("Invariants not satisfied: {}", eval.failed.join(", ")));
         }
 
         Ok(DeletionCOS {
@@ -109,6 +148,7 @@ impl DeletionCOS<Draft> {
             report: None,
             exec: None,
             halt: None,
+            budget: None,
             _s: PhantomData,
         })
     }
@@ -123,6 +163,7 @@ impl DeletionCOS<Draft> {
             report: None,
             exec: None,
             halt: Some(reason),
+            budget: None,
             _s: PhantomData,
         }
     }
@@ -143,6 +184,7 @@ impl DeletionCOS<Validated> {
             report: None,
             exec: None,
             halt: None,
+            budget: None,
             _s: PhantomData,
         }
     }
@@ -153,25 +195,97 @@ impl DeletionCOS<Validated> {
 // ============================================================================
 
 impl DeletionCOS<Planned> {
-    /// Run hypersimulation (convergence check).
-    /// Returns either Converged or Halted.
-    pub fn run_hypersim(self) -> Result<DeletionCOS<Converged>, DeletionCOS<Halted>> {
-        // Simulated: in real implementation, run actual convergence check.
-        let report = ConvergenceReport {
-            rif: 0,
-            converged: true,
-            reason: "All futures converged".into(),
-            structurality: 90,
+    /// Run a budget-bounded parallel hypersimulation: explore candidate
+    /// futures of the plan up to `budget.max_futures` / `budget.max_depth`,
+    /// pruning any that violate the attached invariants. If the surviving
+    /// futures disagree, attempt up to `budget.repair_budget` corrective
+    /// `Repair`s (see `super::repair`), re-running the bounded hypersim
+    /// after each one. Converges to `Executed`'s precursor as soon as a
+    /// repair drives the disagreement to zero, or as soon as the first
+    /// attempt agrees outright; otherwise halts once the repair budget is exhausted.
+    pub fn run_hypersim(self, budget: BudgetUsed) -> Result<DeletionCOS<Converged>, DeletionCOS<Halted>> {
+        let mut invariants = self.invariants.clone();
+        let mut plan = self.plan.clone().unwrap_or_default();
+        let mut remaining_repairs = budget.repair_budget;
+        let mut repairs_consumed = 0;
+
+        // The same context `validate` judged the invariants against, so a
+        // rule like `EvidenceAttached` sees the run's real facts rather than
+        // a default one inside the search.
+        let ctx = EvalContext {
+            evidence_attached: self.evidence.is_some(),
+            ..Default::default()
         };
 
+        let mut outcome = hypersim::run(invariants.as_ref(), &ctx, budget.clone());
+        let mut explored_total = outcome.budget.explored_futures;
+        let mut pruned_total = outcome.budget.pruned_futures;
+
+        while (outcome.report.rif > 0 || !outcome.report.converged) && remaining_repairs > 0 {
+            let strategy = Repair::ORDER[repairs_consumed % Repair::ORDER.len()];
+            let (next_invariants, next_plan, next_budget) = strategy.apply(invariants, plan, outcome.budget);
+            invariants = next_invariants;
+            plan = next_plan;
+            remaining_repairs -= 1;
+            repairs_consumed += 1;
+
+            outcome = hypersim::run(invariants.as_ref(), &ctx, next_budget);
+            explored_total += outcome.budget.explored_futures;
+            pruned_total += outcome.budget.pruned_futures;
+        }
+
+        let mut final_budget = outcome.budget.clone();
+        final_budget.explored_futures = explored_total;
+        final_budget.pruned_futures = pruned_total;
+        final_budget.repairs_consumed = repairs_consumed as u32;
+
+        // Recompute structurality from the totals across every attempt, not
+        // just the last one, so it stays consistent with `final_budget`'s
+        // cumulative explored/pruned counts in the persisted artifact. Like
+        // `hypersim::run`, this measures agreement (not pruning): a clean
+        // convergence (`rif == 0`) always scores 100.
+        let mut final_report = outcome.report;
+        final_report.structurality = if explored_total == 0 {
+            100
+        } else {
+            (((explored_total - final_report.rif) as f64 / explored_total as f64) * 100.0).round() as u8
+        };
+
+        if final_report.rif > 0 || !final_report.converged {
+            let reason = final_report.reason.clone();
+            let (code, message) = if repairs_consumed > 0 {
+                (
+                    "REPAIR_BUDGET_EXHAUSTED",
+                    format!("{reason} after exhausting repair budget of {}", budget.repair_budget),
+                )
+            } else {
+                ("HYPERSIM_DID_NOT_CONVERGE", reason)
+            };
+            return Err(DeletionCOS {
+                account: self.account,
+                evidence: self.evidence,
+                invariants,
+                plan: Some(plan),
+                report: Some(final_report),
+                exec: None,
+                halt: Some(HaltReason {
+                    code: code.into(),
+                    message,
+                }),
+                budget: Some(final_budget),
+                _s: PhantomData,
+            });
+        }
+
         Ok(DeletionCOS {
             account: self.account,
             evidence: self.evidence,
-            invariants: self.invariants,
-            plan: self.plan,
-            report: Some(report),
+            invariants,
+            plan: Some(plan),
+            report: Some(final_report),
             exec: None,
             halt: None,
+            budget: Some(final_budget),
             _s: PhantomData,
         })
     }
@@ -186,6 +300,7 @@ impl DeletionCOS<Planned> {
             report: None,
             exec: None,
             halt: Some(reason),
+            budget: None,
             _s: PhantomData,
         }
     }
@@ -211,11 +326,21 @@ impl DeletionCOS<Converged> {
             report: self.report,
             exec: Some(result),
             halt: None,
+            budget: self.budget,
             _s: PhantomData,
         }
     }
 }
 
+impl<S: crate::ui::StateContract> DeletionCOS<S> {
+    /// The UI contract for this instance's current state, derived the same
+    /// way `ui::contract_for_state` derives it for any other state — so a
+    /// live kernel instance and the frontend always agree on what it permits.
+    pub fn ui_contract(&self) -> crate::ui::UiContract {
+        crate::ui::contract_for_state(S::tag())
+    }
+}
+
 // ============================================================================
 // Executed State: Terminal. Nothing more to do.
 // ============================================================================
@@ -225,6 +350,32 @@ impl DeletionCOS<Executed> {
     pub fn account(&self) -> &AccountId {
         &self.account
     }
+
+    /// Turn a completed run into an appendable, immutable proof of what the
+    /// convergence gate concluded. Satisfies `validate_allow_invariants()` by
+    /// construction: `Executed` is only reachable via a `Converged` run,
+    /// `run_hypersim` only converges when `rif == 0`, and structurality is
+    /// defined so that `rif == 0` always scores 100 (see `hypersim::run`).
+    pub fn to_proof_artifact(&self, invariant_set_id: String, budget: BudgetUsed) -> ProofArtifact {
+        let report = self
+            .report
+            .clone()
+            .expect("Executed state always carries the ConvergenceReport that got it here");
+
+        ProofArtifact {
+            workflow_id: self.account.0.clone(),
+            invariant_set_id,
+            decision: Decision::Allow,
+            rif: report.rif,
+            converged: report.converged,
+            structurality: report.structurality,
+            reason: report.reason,
+            budget,
+            execution_boundary_fingerprint: execution_boundary_fingerprint(self.plan.as_deref()),
+            entry_hash: String::new(),
+            prev_hash: String::new(),
+        }
+    }
 }
 
 // ============================================================================
@@ -236,21 +387,61 @@ impl DeletionCOS<Halted> {
     pub fn reason(&self) -> Option<&HaltReason> {
         self.halt.as_ref()
     }
+
+    /// Turn a halted run into an appendable, immutable proof of why the gate
+    /// stopped it. Satisfies `validate_halt_invariants()` by construction:
+    /// the decision is always `Halt`.
+    pub fn to_proof_artifact(&self, invariant_set_id: String, budget: BudgetUsed) -> ProofArtifact {
+        let halt = self
+            .halt
+            .clone()
+            .expect("Halted state always carries the HaltReason that got it here");
+        let report = self.report.as_ref();
+
+        ProofArtifact {
+            workflow_id: self.account.0.clone(),
+            invariant_set_id,
+            decision: Decision::Halt,
+            rif: report.map(|r| r.rif).unwrap_or(0),
+            converged: report.map(|r| r.converged).unwrap_or(false),
+            structurality: report.map(|r| r.structurality).unwrap_or(0),
+            reason: halt.message,
+            budget,
+            execution_boundary_fingerprint: execution_boundary_fingerprint(self.plan.as_deref()),
+            entry_hash: String::new(),
+            prev_hash: String::new(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::invariant::Invariant;
+
+    fn no_reorders() -> InvariantSet {
+        InvariantSet::new(vec![Invariant::CountBelow { metric: "reorders".into(), limit: 1 }]).unwrap()
+    }
 
     #[test]
     fn typestate_enforces_sequence() {
         let draft = DeletionCOS::new(AccountId("a".into()));
         let draft = draft.attach_evidence("ev".into());
-        let draft = draft.attach_invariants("inv".into());
+        // Forbidding any reordering leaves exactly one surviving path (the
+        // all-"proceed" one) at every depth, so the futures trivially agree.
+        let draft = draft.attach_invariants(no_reorders());
 
         let validated = draft.validate().unwrap();
         let planned = validated.plan("plan".into());
-        let converged = planned.run_hypersim().unwrap();
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 4,
+            repair_budget: 2,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+        let converged = planned.run_hypersim(budget).unwrap();
         let executed = converged.execute();
 
         assert_eq!(executed.account(), &AccountId("a".into()));
@@ -263,6 +454,75 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn run_hypersim_halts_when_surviving_futures_disagree() {
+        let draft = DeletionCOS::new(AccountId("a".into()));
+        let draft = draft.attach_evidence("ev".into());
+        let draft = draft.attach_invariants(InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap());
+
+        let validated = draft.validate().unwrap();
+        let planned = validated.plan("plan".into());
+        // At depth 1 the two root futures (Proceed vs Reorder) are both
+        // terminal and settle on opposite outcomes, so they can't converge.
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 1,
+            repair_budget: 0,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+
+        let halted = planned.run_hypersim(budget).unwrap_err();
+        assert!(halted.reason().is_some());
+    }
+
+    #[test]
+    fn executed_run_produces_a_valid_allow_artifact() {
+        let draft = DeletionCOS::new(AccountId("a".into()));
+        let draft = draft.attach_evidence("ev".into());
+        let invariants = no_reorders();
+        let invariant_set_id = invariants.id.clone();
+        let draft = draft.attach_invariants(invariants);
+        let validated = draft.validate().unwrap();
+        let planned = validated.plan("plan".into());
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 4,
+            repair_budget: 2,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+        let converged = planned.run_hypersim(budget.clone()).unwrap();
+        let executed = converged.execute();
+
+        let artifact = executed.to_proof_artifact(invariant_set_id, budget);
+        assert!(artifact.validate_allow_invariants().is_ok());
+    }
+
+    #[test]
+    fn halted_run_produces_a_valid_halt_artifact() {
+        let draft = DeletionCOS::new(AccountId("a".into()));
+        let draft = draft.attach_evidence("ev".into());
+        let reason = HaltReason {
+            code: "USER_CANCEL".into(),
+            message: "User cancelled".into(),
+        };
+        let halted = draft.halt(reason);
+
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 4,
+            repair_budget: 2,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+        let artifact = halted.to_proof_artifact("inv_v1".into(), budget);
+        assert!(artifact.validate_halt_invariants().is_ok());
+    }
+
     #[test]
     fn halt_is_always_available() {
         let draft = DeletionCOS::new(AccountId("a".into()));
@@ -273,4 +533,32 @@ mod tests {
         let halted = draft.halt(reason);
         assert!(halted.reason().is_some());
     }
+
+    #[test]
+    fn repair_converges_when_tightening_invariants_resolves_the_disagreement() {
+        let draft = DeletionCOS::new(AccountId("a".into()));
+        let draft = draft.attach_evidence("ev".into());
+        // An empty rule set holds vacuously, so nothing is pruned at first: the
+        // "Proceed" and "Reorder" root futures both survive to depth 1 and
+        // settle on opposite outcomes.
+        let draft = draft.attach_invariants(InvariantSet::new(vec![]).unwrap());
+
+        let validated = draft.validate().unwrap();
+        let planned = validated.plan("delete then archive".into());
+        // `Repair::TightenInvariants` adds a `CountBelow { metric: "reorders",
+        // limit: 1 }` rule, which prunes the "Reorder" future on the repaired
+        // attempt and leaves only the agreeing "Proceed" one.
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 1,
+            repair_budget: 1,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+
+        let converged = planned.run_hypersim(budget).unwrap();
+        let executed = converged.execute();
+        assert_eq!(executed.budget.as_ref().unwrap().repairs_consumed, 1);
+    }
 }
@@ -0,0 +1,234 @@
+/// Budget-bounded parallel search over candidate deletion futures.
+///
+/// A candidate future is a path of decisions branching off the plan: at each
+/// step the search considers letting the plan `Proceed` as written, or
+/// `Reorder` it relative to its neighbours. Starting from the root, the
+/// search pops a node from a shared frontier, expands its two children, and
+/// for each child either prunes it (it violates the attached invariants) or
+/// keeps it — pushing it back onto the frontier if it hasn't reached
+/// `max_depth` yet, or recording its terminal outcome if it has. Exploration
+/// stops once `max_futures` surviving futures have been found or the
+/// frontier drains.
+///
+/// The frontier is a shared queue guarded by a `Mutex` plus a `Condvar` to
+/// wake idle workers as soon as new nodes land, in a worker-pool pattern.
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use crate::proof::BudgetUsed;
+
+use super::deletion::ConvergenceReport;
+use super::invariant::{self, EvalContext, InvariantSet};
+
+/// One step along a candidate future.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Proceed,
+    Reorder,
+}
+
+/// A path of decisions from the root of the search tree.
+#[derive(Debug, Clone)]
+struct FutureNode {
+    path: Vec<Step>,
+}
+
+impl FutureNode {
+    fn root() -> Self {
+        FutureNode { path: Vec::new() }
+    }
+
+    fn depth(&self) -> u32 {
+        self.path.len() as u32
+    }
+
+    fn child(&self, step: Step) -> Self {
+        let mut path = self.path.clone();
+        path.push(step);
+        FutureNode { path }
+    }
+
+    /// The future's net decision: whether `Proceed` steps outnumber
+    /// `Reorder` steps along its path. Two terminal futures converge only if
+    /// they settle on the same net decision.
+    fn proceed_dominant(&self) -> bool {
+        let proceed = self.path.iter().filter(|s| **s == Step::Proceed).count();
+        let reorder = self.path.iter().filter(|s| **s == Step::Reorder).count();
+        proceed >= reorder
+    }
+}
+
+/// Judges whether a candidate future still satisfies the run's invariants,
+/// via the same structured evaluator `DeletionCOS::validate` uses. `None`
+/// means no invariants were attached, so every future holds. `base_ctx`
+/// carries the run's real facts (e.g. `evidence_attached`); only the
+/// per-future `"reorders"` metric is added on top of it, so an invariant
+/// like `EvidenceAttached` is judged against the run, not a default context.
+fn future_holds(invariants: Option<&InvariantSet>, base_ctx: &EvalContext, node: &FutureNode) -> bool {
+    let Some(invariants) = invariants else {
+        return true;
+    };
+
+    let reorders = node.path.iter().filter(|s| **s == Step::Reorder).count() as u64;
+    let mut ctx = base_ctx.clone();
+    ctx.metrics.insert("reorders".to_string(), reorders);
+
+    invariant::evaluate(invariants, &ctx).satisfied
+}
+
+/// Shared search frontier.
+struct Frontier {
+    queue: VecDeque<FutureNode>,
+    /// Nodes popped but not yet re-queued or finalized, so other idle
+    /// workers don't mistake a momentarily empty queue for a drained one.
+    in_flight: u32,
+    explored_futures: u32,
+    pruned_futures: u32,
+    terminal_outcomes: Vec<bool>,
+    /// True once `max_futures` surviving futures were found before the
+    /// frontier fully drained.
+    budget_exhausted: bool,
+    done: bool,
+}
+
+/// Result of a bounded hypersimulation.
+pub struct HypersimOutcome {
+    pub report: ConvergenceReport,
+    pub budget: BudgetUsed,
+}
+
+/// Run a budget-bounded, multi-threaded search over candidate futures,
+/// pruning those that violate `invariants`, filling in `budget.explored_futures` /
+/// `budget.pruned_futures` and a `ConvergenceReport` as it goes.
+///
+/// `budget.max_futures` and `budget.max_depth` bound the search;
+/// `budget.explored_futures` / `budget.pruned_futures` are overwritten with
+/// the counts actually observed. `ctx` carries the run's real facts (e.g.
+/// `evidence_attached`) that every candidate future is judged against, on
+/// top of its own per-future `"reorders"` metric.
+pub fn run(invariants: Option<&InvariantSet>, ctx: &EvalContext, mut budget: BudgetUsed) -> HypersimOutcome {
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+
+    let shared = (
+        Mutex::new(Frontier {
+            queue: VecDeque::from([FutureNode::root()]),
+            in_flight: 0,
+            explored_futures: 0,
+            pruned_futures: 0,
+            terminal_outcomes: Vec::new(),
+            budget_exhausted: false,
+            done: false,
+        }),
+        Condvar::new(),
+    );
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let shared = &shared;
+            scope.spawn(move || worker_loop(shared, invariants, ctx, budget.max_futures, budget.max_depth));
+        }
+    });
+
+    let frontier = shared.0.into_inner().unwrap();
+
+    budget.explored_futures = frontier.explored_futures;
+    budget.pruned_futures = frontier.pruned_futures;
+
+    // `terminal_outcomes` is filled by N worker threads under the mutex in
+    // scheduling-dependent order, so picking a "consensus" by position (e.g.
+    // the first outcome recorded) would make `rif` — and the reason/
+    // structurality derived from it — nondeterministic across runs of the
+    // same (plan, invariants, budget). Count the minority side instead: it's
+    // order-independent, since it only depends on how many outcomes landed
+    // on each side, not which one happened to be recorded first.
+    let proceed_count = frontier.terminal_outcomes.iter().filter(|outcome| **outcome).count();
+    let reorder_count = frontier.terminal_outcomes.len() - proceed_count;
+    let rif = proceed_count.min(reorder_count) as u32;
+    let converged = rif == 0 && !frontier.budget_exhausted;
+
+    // Structurality measures agreement among the explored futures, not how
+    // much pruning happened — pruning is the invariants doing their job, so
+    // it must not drag the score down. A fully agreeing run (`rif == 0`)
+    // always scores 100; each disagreeing future costs a proportional share.
+    let structurality = if frontier.explored_futures == 0 {
+        100
+    } else {
+        (((frontier.explored_futures - rif) as f64 / frontier.explored_futures as f64) * 100.0).round() as u8
+    };
+
+    let reason = if frontier.budget_exhausted {
+        "Budget exhausted before the frontier drained".to_string()
+    } else if rif > 0 {
+        format!("{rif} surviving future(s) disagree on outcome")
+    } else {
+        "All futures converged".to_string()
+    };
+
+    HypersimOutcome {
+        report: ConvergenceReport {
+            rif,
+            converged,
+            reason,
+            structurality,
+        },
+        budget,
+    }
+}
+
+fn worker_loop(
+    shared: &(Mutex<Frontier>, Condvar),
+    invariants: Option<&InvariantSet>,
+    ctx: &EvalContext,
+    max_futures: u32,
+    max_depth: u32,
+) {
+    let (lock, cv) = shared;
+
+    loop {
+        let node = {
+            let mut frontier = lock.lock().unwrap();
+            loop {
+                if frontier.done {
+                    return;
+                }
+                if frontier.explored_futures >= max_futures {
+                    frontier.budget_exhausted = !frontier.queue.is_empty() || frontier.in_flight > 0;
+                    frontier.done = true;
+                    cv.notify_all();
+                    return;
+                }
+                if let Some(node) = frontier.queue.pop_front() {
+                    frontier.in_flight += 1;
+                    break node;
+                }
+                if frontier.in_flight == 0 {
+                    frontier.done = true;
+                    cv.notify_all();
+                    return;
+                }
+                frontier = cv.wait(frontier).unwrap();
+            }
+        };
+
+        let proceed_child = node.child(Step::Proceed);
+        let reorder_child = node.child(Step::Reorder);
+
+        let mut frontier = lock.lock().unwrap();
+        frontier.in_flight -= 1;
+
+        for child in [proceed_child, reorder_child] {
+            if !future_holds(invariants, ctx, &child) {
+                frontier.pruned_futures += 1;
+                continue;
+            }
+            frontier.explored_futures += 1;
+            if child.depth() >= max_depth {
+                frontier.terminal_outcomes.push(child.proceed_dominant());
+            } else {
+                frontier.queue.push_back(child);
+            }
+        }
+        cv.notify_all();
+    }
+}
@@ -0,0 +1,189 @@
+/// Structured, versioned invariant predicates.
+///
+/// Replaces the free-form `invariants: String` the kernel used to carry
+/// around: an `InvariantSet` is a named, executable rule list whose `id` is
+/// a stable hash of its own NDJSON serialization, so two runs judged by the
+/// "same" invariants are provably judged by the same rules, not just a
+/// label that happens to match.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// A single structured predicate, or a combination of others.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Invariant {
+    /// Evidence must have been attached to the run.
+    EvidenceAttached,
+    /// The context field at `path` must equal `value`.
+    FieldEquals { path: String, value: String },
+    /// The context metric named `metric` must be strictly below `limit`.
+    CountBelow { metric: String, limit: u64 },
+    /// All of the nested rules must hold.
+    AllOf(Vec<Invariant>),
+    /// At least one of the nested rules must hold.
+    AnyOf(Vec<Invariant>),
+}
+
+/// A named, hashable set of invariants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvariantSet {
+    /// Stable hash of the rules' NDJSON serialization. Two sets with the
+    /// same rules always get the same id; any change to a rule changes it.
+    pub id: String,
+    pub rules: Vec<Invariant>,
+}
+
+impl InvariantSet {
+    /// Build a set and stamp it with its content-derived id.
+    pub fn new(rules: Vec<Invariant>) -> Result<Self, String> {
+        let id = Self::hash_rules(&rules)?;
+        Ok(InvariantSet { id, rules })
+    }
+
+    /// Serialize the rules to NDJSON, one rule per line.
+    pub fn to_ndjson(&self) -> Result<String, String> {
+        Self::rules_to_ndjson(&self.rules)
+    }
+
+    fn rules_to_ndjson(rules: &[Invariant]) -> Result<String, String> {
+        rules
+            .iter()
+            .map(|rule| serde_json::to_string(rule).map_err(|e| format!("serialize: {e}")))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|lines| lines.join("\n"))
+    }
+
+    fn hash_rules(rules: &[Invariant]) -> Result<String, String> {
+        let ndjson = Self::rules_to_ndjson(rules)?;
+        let mut hasher = Sha256::new();
+        hasher.update(ndjson.as_bytes());
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+}
+
+/// Facts about a run, or a candidate future of one, that invariants are
+/// judged against.
+#[derive(Debug, Clone, Default)]
+pub struct EvalContext {
+    pub evidence_attached: bool,
+    pub fields: BTreeMap<String, String>,
+    pub metrics: BTreeMap<String, u64>,
+}
+
+/// Result of evaluating an `InvariantSet` against an `EvalContext`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalReport {
+    pub satisfied: bool,
+    /// Human-readable description of each rule that did not hold.
+    pub failed: Vec<String>,
+}
+
+/// Evaluate every rule in `set` against `ctx`, collecting a description of
+/// each one that fails rather than stopping at the first.
+pub fn evaluate(set: &InvariantSet, ctx: &EvalContext) -> EvalReport {
+    let mut failed = Vec::new();
+    let satisfied = set.rules.iter().fold(true, |acc, rule| holds(rule, ctx, &mut failed) & acc);
+    EvalReport { satisfied, failed }
+}
+
+fn holds(rule: &Invariant, ctx: &EvalContext, failed: &mut Vec<String>) -> bool {
+    match rule {
+        Invariant::EvidenceAttached => {
+            let ok = ctx.evidence_attached;
+            if !ok {
+                failed.push("EvidenceAttached".to_string());
+            }
+            ok
+        }
+        Invariant::FieldEquals { path, value } => {
+            let ok = ctx.fields.get(path).is_some_and(|v| v == value);
+            if !ok {
+                failed.push(format!("FieldEquals{{path: {path}, value: {value}}}"));
+            }
+            ok
+        }
+        Invariant::CountBelow { metric, limit } => {
+            // A context that doesn't track this metric yet (e.g. validating
+            // a run before any futures were explored) can't violate a bound
+            // on it, so treat an absent metric as vacuously satisfied.
+            let ok = ctx.metrics.get(metric).map(|v| v < limit).unwrap_or(true);
+            if !ok {
+                failed.push(format!("CountBelow{{metric: {metric}, limit: {limit}}}"));
+            }
+            ok
+        }
+        Invariant::AllOf(rules) => {
+            // Evaluate every nested rule so all failures are reported, not just the first.
+            rules.iter().fold(true, |acc, rule| holds(rule, ctx, failed) & acc)
+        }
+        Invariant::AnyOf(rules) => {
+            let mut nested_failed = Vec::new();
+            let ok = rules.iter().fold(false, |acc, rule| holds(rule, ctx, &mut nested_failed) | acc);
+            if !ok {
+                failed.extend(nested_failed);
+            }
+            ok
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_rules_hash_to_the_same_id() {
+        let a = InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap();
+        let b = InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap();
+        assert_eq!(a.id, b.id);
+    }
+
+    #[test]
+    fn different_rules_hash_to_different_ids() {
+        let a = InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap();
+        let b = InvariantSet::new(vec![Invariant::CountBelow { metric: "x".into(), limit: 1 }]).unwrap();
+        assert_ne!(a.id, b.id);
+    }
+
+    #[test]
+    fn evidence_attached_checks_context() {
+        let set = InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap();
+        let ctx = EvalContext::default();
+        let report = evaluate(&set, &ctx);
+        assert!(!report.satisfied);
+        assert_eq!(report.failed, vec!["EvidenceAttached".to_string()]);
+    }
+
+    #[test]
+    fn count_below_passes_under_the_limit() {
+        let set = InvariantSet::new(vec![Invariant::CountBelow { metric: "reorders".into(), limit: 1 }]).unwrap();
+        let mut ctx = EvalContext::default();
+        ctx.metrics.insert("reorders".into(), 0);
+        assert!(evaluate(&set, &ctx).satisfied);
+    }
+
+    #[test]
+    fn all_of_requires_every_rule() {
+        let set = InvariantSet::new(vec![Invariant::AllOf(vec![
+            Invariant::EvidenceAttached,
+            Invariant::CountBelow { metric: "reorders".into(), limit: 1 },
+        ])])
+        .unwrap();
+        let mut ctx = EvalContext::default();
+        ctx.evidence_attached = true;
+        ctx.metrics.insert("reorders".into(), 5);
+        assert!(!evaluate(&set, &ctx).satisfied);
+    }
+
+    #[test]
+    fn any_of_requires_only_one_rule() {
+        let set = InvariantSet::new(vec![Invariant::AnyOf(vec![
+            Invariant::EvidenceAttached,
+            Invariant::CountBelow { metric: "reorders".into(), limit: 1 },
+        ])])
+        .unwrap();
+        let mut ctx = EvalContext::default();
+        ctx.metrics.insert("reorders".into(), 0);
+        assert!(evaluate(&set, &ctx).satisfied);
+    }
+}
@@ -1,4 +1,10 @@
 pub mod deletion;
+mod hypersim;
+pub mod invariant;
+pub mod repair;
+
+pub use invariant::{EvalContext, EvalReport, Invariant, InvariantSet};
+pub use repair::Repair;
 
 pub use deletion::{AccountId, ConvergenceReport, DeletionCOS, ExecutionResult, HaltReason};
 pub use deletion::{Converged, Draft, Executed, Halted, Planned, Validated};
@@ -0,0 +1,138 @@
+/// Pluggable repair strategies attempted between a non-converging hypersim
+/// run and the next, each producing the invariants, plan and budget the next
+/// attempt runs against.
+use super::invariant::{Invariant, InvariantSet};
+use crate::proof::BudgetUsed;
+
+/// A single corrective action `DeletionCOS<Planned>::run_hypersim` can take
+/// when the surviving futures disagree, before falling through to `Halted`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    /// Tighten the invariant set: forbid any `Reorder` step outright by
+    /// adding a `CountBelow { metric: "reorders", limit: 1 }` rule, pruning
+    /// every future that disagrees from future exploration.
+    TightenInvariants,
+    /// Shrink the search by one level of depth, dropping the deepest (and
+    /// most ambiguous) branch of the tree from consideration.
+    DropAmbiguousBranch,
+    /// Roll back the plan's last step and re-attempt hypersim against the
+    /// shorter plan. Recorded on the run so the audit trail shows which plan
+    /// actually converged. The invariants and budget pass through unchanged,
+    /// and `hypersim::run` judges abstract Proceed/Reorder steps rather than
+    /// the plan's text, so the re-attempt searches the same futures as the
+    /// attempt before it.
+    RollbackLastStep,
+}
+
+impl Repair {
+    /// The fixed order repairs are attempted in: tightening the invariants
+    /// is cheapest and most likely to resolve a disagreement, so it's tried
+    /// before shrinking the search or rolling back the plan itself.
+    pub const ORDER: [Repair; 3] = [
+        Repair::TightenInvariants,
+        Repair::DropAmbiguousBranch,
+        Repair::RollbackLastStep,
+    ];
+
+    /// Apply this strategy, producing the invariants/plan/budget the next
+    /// hypersim attempt should run against.
+    pub fn apply(
+        self,
+        invariants: Option<InvariantSet>,
+        plan: String,
+        mut budget: BudgetUsed,
+    ) -> (Option<InvariantSet>, String, BudgetUsed) {
+        match self {
+            Repair::TightenInvariants => {
+                let tightened = match invariants {
+                    Some(set) => {
+                        let mut rules = set.rules;
+                        rules.push(Invariant::CountBelow {
+                            metric: "reorders".into(),
+                            limit: 1,
+                        });
+                        InvariantSet::new(rules)
+                            .expect("re-hashing an extended rule set cannot fail")
+                    }
+                    None => InvariantSet::new(vec![Invariant::CountBelow {
+                        metric: "reorders".into(),
+                        limit: 1,
+                    }])
+                    .expect("a single rule always hashes"),
+                };
+                (Some(tightened), plan, budget)
+            }
+            Repair::DropAmbiguousBranch => {
+                budget.max_depth = budget.max_depth.saturating_sub(1).max(1);
+                (invariants, plan, budget)
+            }
+            Repair::RollbackLastStep => {
+                let rolled_back = match plan.rsplit_once(' ') {
+                    Some((rest, _last_step)) => rest.to_string(),
+                    None => plan,
+                };
+                (invariants, rolled_back, budget)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tighten_invariants_adds_a_reorder_bound() {
+        let (invariants, _plan, _budget) = Repair::TightenInvariants.apply(
+            None,
+            "plan".into(),
+            BudgetUsed {
+                max_futures: 64,
+                max_depth: 4,
+                repair_budget: 1,
+                explored_futures: 0,
+                pruned_futures: 0,
+                repairs_consumed: 0,
+            },
+        );
+        let rules = invariants.unwrap().rules;
+        assert!(rules.contains(&Invariant::CountBelow {
+            metric: "reorders".into(),
+            limit: 1
+        }));
+    }
+
+    #[test]
+    fn drop_ambiguous_branch_shrinks_depth_but_not_below_one() {
+        let (_invariants, _plan, budget) = Repair::DropAmbiguousBranch.apply(
+            None,
+            "plan".into(),
+            BudgetUsed {
+                max_futures: 64,
+                max_depth: 1,
+                repair_budget: 1,
+                explored_futures: 0,
+                pruned_futures: 0,
+                repairs_consumed: 0,
+            },
+        );
+        assert_eq!(budget.max_depth, 1);
+    }
+
+    #[test]
+    fn rollback_last_step_drops_the_final_token() {
+        let (_invariants, plan, _budget) = Repair::RollbackLastStep.apply(
+            None,
+            "delete then archive".into(),
+            BudgetUsed {
+                max_futures: 64,
+                max_depth: 4,
+                repair_budget: 1,
+                explored_futures: 0,
+                pruned_futures: 0,
+                repairs_consumed: 0,
+            },
+        );
+        assert_eq!(plan, "delete then");
+    }
+}
@@ -1,15 +1,29 @@
 /// Append-only NDJSON storage for proof artifacts.
 /// Never modify entries in place. Only append.
 /// Used for permanent audit trail.
+///
+/// Entries are chained by hash: each line's `entry_hash` covers its own
+/// canonical contents plus the previous line's `entry_hash`, so editing,
+/// reordering, or truncating the *head* of the log breaks the chain and is
+/// detectable by `verify_chain`. Dropping *trailing* entries still leaves an
+/// internally consistent prefix, so `verify_chain` alone can't see that —
+/// callers who need to detect a shortened tail must compare the `ChainHead`
+/// it returns against a separately recorded expected head (see
+/// `verify_chain`'s doc comment).
 
 use super::artifact::ProofArtifact;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 /// NDJSON handler: newline-delimited JSON.
 /// Each line is a complete, independent ProofArtifact.
 pub struct Ndjson;
 
 impl Ndjson {
+    /// `prev_hash` of the first entry in a chain. Not a valid SHA-256 digest
+    /// of anything; it exists purely to anchor the chain's genesis.
+    pub const GENESIS_HASH: &'static str = "0000000000000000000000000000000000000000000000000000000000000000";
+
     /// Serialize a single proof artifact to NDJSON line.
     pub fn serialize_entry(a: &ProofArtifact) -> Result<String, String> {
         serde_json::to_string(a).map_err(|e| format!("serialize: {e}"))
@@ -25,6 +39,91 @@ impl Ndjson {
     pub fn is_valid_json(line: &str) -> bool {
         serde_json::from_str::<Value>(line).is_ok()
     }
+
+    /// Canonical bytes of an artifact for hashing: its JSON serialization with
+    /// keys sorted (via `serde_json::Map`'s default `BTreeMap` backing) and
+    /// `entry_hash` cleared so the field doesn't hash itself.
+    fn canonical_bytes(a: &ProofArtifact) -> Result<Vec<u8>, String> {
+        let mut value = serde_json::to_value(a).map_err(|e| format!("serialize: {e}"))?;
+        if let Value::Object(ref mut map) = value {
+            map.insert("entry_hash".to_string(), Value::String(String::new()));
+        }
+        serde_json::to_vec(&value).map_err(|e| format!("serialize: {e}"))
+    }
+
+    /// Digest of an entry's canonical bytes concatenated with `prev_hash`.
+    fn digest(canonical: &[u8], prev_hash: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(canonical);
+        hasher.update(prev_hash.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Append `a` to the chain following `prev_hash` (the previous line's
+    /// `entry_hash`, or `GENESIS_HASH` for the first entry), returning the
+    /// serialized NDJSON line with `prev_hash`/`entry_hash` populated.
+    pub fn append_entry(prev_hash: &str, a: &ProofArtifact) -> Result<String, String> {
+        let mut entry = a.clone();
+        entry.prev_hash = prev_hash.to_string();
+        entry.entry_hash.clear();
+
+        let canonical = Self::canonical_bytes(&entry)?;
+        entry.entry_hash = Self::digest(&canonical, prev_hash);
+
+        Self::serialize_entry(&entry)
+    }
+
+    /// Recompute and check every entry's hash chain, failing loudly on the
+    /// first break (a tampered field, a reordered line, or a truncated head)
+    /// so a CI gate can detect it. On success, returns the chain's
+    /// `ChainHead` (the last entry's hash and how many entries were seen) —
+    /// compare it against a separately recorded expected head to also catch
+    /// *trailing* truncation, since a dropped suffix still leaves a
+    /// internally-consistent prefix that this function alone would accept.
+    pub fn verify_chain<'a>(lines: impl Iterator<Item = &'a str>) -> Result<ChainHead, String> {
+        let mut expected_prev_hash = Self::GENESIS_HASH.to_string();
+        let mut len = 0usize;
+
+        for (i, line) in lines.enumerate() {
+            let entry = Self::deserialize_entry(line)
+                .map_err(|e| format!("entry {i}: {e}"))?;
+
+            if entry.prev_hash != expected_prev_hash {
+                return Err(format!(
+                    "entry {i}: prev_hash mismatch (expected {expected_prev_hash}, got {})",
+                    entry.prev_hash
+                ));
+            }
+
+            let mut canonical_source = entry.clone();
+            canonical_source.entry_hash.clear();
+            let canonical = Self::canonical_bytes(&canonical_source)
+                .map_err(|e| format!("entry {i}: {e}"))?;
+            let recomputed = Self::digest(&canonical, &entry.prev_hash);
+
+            if recomputed != entry.entry_hash {
+                return Err(format!("entry {i}: entry_hash mismatch, chain tampered"));
+            }
+
+            expected_prev_hash = entry.entry_hash;
+            len = i + 1;
+        }
+
+        Ok(ChainHead {
+            entry_hash: expected_prev_hash,
+            len,
+        })
+    }
+}
+
+/// The tail of a verified chain: its last entry's hash and how many entries
+/// were walked. `verify_chain` returns this so a caller holding a
+/// separately-recorded expected head can detect trailing truncation, which
+/// an internally-consistent-prefix check can't see on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHead {
+    pub entry_hash: String,
+    pub len: usize,
 }
 
 #[cfg(test)]
@@ -47,8 +146,11 @@ mod tests {
                 repair_budget: 2,
                 explored_futures: 12,
                 pruned_futures: 12,
+                repairs_consumed: 0,
             },
             execution_boundary_fingerprint: "exec_v1".into(),
+            entry_hash: String::new(),
+            prev_hash: String::new(),
         }
     }
 
@@ -78,4 +180,59 @@ mod tests {
         let line = "not json";
         assert!(!Ndjson::is_valid_json(line));
     }
+
+    #[test]
+    fn chain_of_entries_verifies() {
+        let line1 = Ndjson::append_entry(Ndjson::GENESIS_HASH, &sample()).unwrap();
+        let entry1 = Ndjson::deserialize_entry(&line1).unwrap();
+        let line2 = Ndjson::append_entry(&entry1.entry_hash, &sample()).unwrap();
+
+        assert!(Ndjson::verify_chain([line1.as_str(), line2.as_str()].into_iter()).is_ok());
+    }
+
+    #[test]
+    fn tampered_entry_breaks_chain() {
+        let line1 = Ndjson::append_entry(Ndjson::GENESIS_HASH, &sample()).unwrap();
+        let mut entry1 = Ndjson::deserialize_entry(&line1).unwrap();
+        entry1.rif = 99; // tamper with a field after hashing
+        let tampered = Ndjson::serialize_entry(&entry1).unwrap();
+
+        assert!(Ndjson::verify_chain([tampered.as_str()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn reordered_entries_break_chain() {
+        let line1 = Ndjson::append_entry(Ndjson::GENESIS_HASH, &sample()).unwrap();
+        let entry1 = Ndjson::deserialize_entry(&line1).unwrap();
+        let line2 = Ndjson::append_entry(&entry1.entry_hash, &sample()).unwrap();
+
+        assert!(Ndjson::verify_chain([line2.as_str(), line1.as_str()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn truncated_chain_is_detected() {
+        let line1 = Ndjson::append_entry(Ndjson::GENESIS_HASH, &sample()).unwrap();
+        let entry1 = Ndjson::deserialize_entry(&line1).unwrap();
+        let line2 = Ndjson::append_entry(&entry1.entry_hash, &sample()).unwrap();
+
+        // Dropping the first line makes the second line's prev_hash orphaned.
+        assert!(Ndjson::verify_chain([line2.as_str()].into_iter()).is_err());
+    }
+
+    #[test]
+    fn trailing_truncation_is_detected_via_chain_head() {
+        let line1 = Ndjson::append_entry(Ndjson::GENESIS_HASH, &sample()).unwrap();
+        let entry1 = Ndjson::deserialize_entry(&line1).unwrap();
+        let line2 = Ndjson::append_entry(&entry1.entry_hash, &sample()).unwrap();
+        let entry2 = Ndjson::deserialize_entry(&line2).unwrap();
+
+        // Dropping the trailing line still leaves an internally consistent
+        // prefix, so verify_chain alone succeeds...
+        let truncated_head = Ndjson::verify_chain([line1.as_str()].into_iter()).unwrap();
+        // ...but its ChainHead doesn't match the expected head recorded
+        // after the real last append, so a caller comparing against that
+        // expectation catches the truncation.
+        assert_ne!(truncated_head.entry_hash, entry2.entry_hash);
+        assert_eq!(truncated_head.len, 1);
+    }
 }
@@ -23,6 +23,8 @@ pub struct BudgetUsed {
     pub explored_futures: u32,
     /// Futures pruned (violate invariants)
     pub pruned_futures: u32,
+    /// Repairs actually consumed after the first hypersim attempt failed to converge
+    pub repairs_consumed: u32,
 }
 
 /// Immutable proof that a workflow instance satisfied all gates.
@@ -56,6 +58,13 @@ pub struct ProofArtifact {
     /// Hash/fingerprint of execution boundary contract
     /// In production: hash of boundary function signature + policy gate version
     pub execution_boundary_fingerprint: String,
+
+    /// SHA-256 digest of this entry's canonical form concatenated with `prev_hash`.
+    /// Cleared to empty before hashing to avoid self-reference. See `Ndjson::append_entry`.
+    pub entry_hash: String,
+
+    /// `entry_hash` of the previous entry in the chain, or `Ndjson::GENESIS_HASH` for the first entry.
+    pub prev_hash: String,
 }
 
 impl ProofArtifact {
@@ -91,6 +100,7 @@ impl ProofArtifact {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::append_only::Ndjson;
 
     fn allow() -> ProofArtifact {
         ProofArtifact {
@@ -107,8 +117,11 @@ mod tests {
                 repair_budget: 2,
                 explored_futures: 12,
                 pruned_futures: 12,
+                repairs_consumed: 0,
             },
             execution_boundary_fingerprint: "exec_v1".into(),
+            entry_hash: String::new(),
+            prev_hash: Ndjson::GENESIS_HASH.to_string(),
         }
     }
 
@@ -127,8 +140,11 @@ mod tests {
                 repair_budget: 2,
                 explored_futures: 30,
                 pruned_futures: 0,
+                repairs_consumed: 0,
             },
             execution_boundary_fingerprint: "exec_v1".into(),
+            entry_hash: String::new(),
+            prev_hash: Ndjson::GENESIS_HASH.to_string(),
         }
     }
 
@@ -3,6 +3,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::domain::deletion::{Converged, Draft, Executed, Halted, Planned, Validated};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum UiAction {
     AttachEvidence,
@@ -32,16 +34,89 @@ pub struct UiContract {
     pub allowed: Vec<UiAction>,
 }
 
+/// Implemented by each zero-sized typestate marker in `domain::deletion` to
+/// declare what the UI tag and allowed actions are for that state. This is
+/// the single source of truth `contract_for_state` dispatches through. The
+/// compiler does not tie these lists to the methods on `DeletionCOS<S>`; the
+/// `*_actions_are_all_reachable` tests below drive each advertised action.
+pub trait StateContract {
+    /// The `UiStateTag` this marker corresponds to.
+    fn tag() -> UiStateTag;
+
+    /// Actions the kernel permits the frontend to render in this state.
+    fn actions() -> Vec<UiAction>;
+}
+
+impl StateContract for Draft {
+    fn tag() -> UiStateTag {
+        UiStateTag::Draft
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![UiAction::AttachEvidence, UiAction::AttachInvariants, UiAction::Validate]
+    }
+}
+
+impl StateContract for Validated {
+    fn tag() -> UiStateTag {
+        UiStateTag::Validated
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![UiAction::Plan]
+    }
+}
+
+impl StateContract for Planned {
+    fn tag() -> UiStateTag {
+        UiStateTag::Planned
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![UiAction::RunHypersim, UiAction::Halt]
+    }
+}
+
+impl StateContract for Converged {
+    fn tag() -> UiStateTag {
+        UiStateTag::Converged
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![UiAction::Execute]
+    }
+}
+
+impl StateContract for Executed {
+    fn tag() -> UiStateTag {
+        UiStateTag::Executed
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![]
+    }
+}
+
+impl StateContract for Halted {
+    fn tag() -> UiStateTag {
+        UiStateTag::Halted
+    }
+
+    fn actions() -> Vec<UiAction> {
+        vec![]
+    }
+}
+
 /// Generate UI contract from core state.
 /// This function is the single source of truth for what the UI can show.
 pub fn contract_for_state(tag: UiStateTag) -> UiContract {
     let allowed = match tag {
-        UiStateTag::Draft => vec![UiAction::AttachEvidence, UiAction::AttachInvariants, UiAction::Validate],
-        UiStateTag::Validated => vec![UiAction::Plan],
-        UiStateTag::Planned => vec![UiAction::RunHypersim, UiAction::Halt],
-        UiStateTag::Converged => vec![UiAction::Execute],
-        UiStateTag::Executed => vec![],
-        UiStateTag::Halted => vec![],
+        UiStateTag::Draft => Draft::actions(),
+        UiStateTag::Validated => Validated::actions(),
+        UiStateTag::Planned => Planned::actions(),
+        UiStateTag::Converged => Converged::actions(),
+        UiStateTag::Executed => Executed::actions(),
+        UiStateTag::Halted => Halted::actions(),
     };
 
     UiContract { tag, allowed }
@@ -75,4 +150,104 @@ mod tests {
         assert!(!planned.allowed.iter().any(|a| *a == UiAction::Execute));
         assert!(converged.allowed.iter().any(|a| *a == UiAction::Execute));
     }
+
+    /// Every `UiAction` a state advertises must correspond to a real kernel
+    /// method reachable from that state — drives the whole sequence to prove
+    /// it, rather than trusting the advertised list on its own.
+    #[test]
+    fn draft_actions_are_all_reachable() {
+        use crate::domain::deletion::{AccountId, DeletionCOS};
+        use crate::domain::invariant::{Invariant, InvariantSet};
+
+        let actions = Draft::actions();
+        assert!(actions.contains(&UiAction::AttachEvidence));
+        assert!(actions.contains(&UiAction::AttachInvariants));
+        assert!(actions.contains(&UiAction::Validate));
+
+        let cos = DeletionCOS::new(AccountId("a".into())); // AttachEvidence/AttachInvariants/Validate below
+        let cos = cos.attach_evidence("ev".into());
+        let cos = cos.attach_invariants(InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap());
+        assert!(cos.validate().is_ok());
+    }
+
+    fn planned(account: &str, invariants: crate::domain::invariant::InvariantSet) -> crate::domain::deletion::DeletionCOS<Planned> {
+        use crate::domain::deletion::{AccountId, DeletionCOS};
+
+        DeletionCOS::new(AccountId(account.into()))
+            .attach_evidence("ev".into())
+            .attach_invariants(invariants)
+            .validate()
+            .unwrap()
+            .plan("plan".into())
+    }
+
+    fn no_op_invariants() -> crate::domain::invariant::InvariantSet {
+        use crate::domain::invariant::{Invariant, InvariantSet};
+        InvariantSet::new(vec![Invariant::EvidenceAttached]).unwrap()
+    }
+
+    fn no_reorders_invariants() -> crate::domain::invariant::InvariantSet {
+        use crate::domain::invariant::{Invariant, InvariantSet};
+        InvariantSet::new(vec![Invariant::CountBelow { metric: "reorders".into(), limit: 1 }]).unwrap()
+    }
+
+    #[test]
+    fn planned_actions_are_all_reachable() {
+        use crate::domain::deletion::HaltReason;
+        use crate::proof::BudgetUsed;
+
+        let actions = Planned::actions();
+        assert!(actions.contains(&UiAction::RunHypersim));
+        assert!(actions.contains(&UiAction::Halt));
+
+        let halted = planned("a", no_op_invariants()).halt(HaltReason {
+            code: "USER_CANCEL".into(),
+            message: "cancelled".into(),
+        });
+        assert!(halted.reason().is_some());
+
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 4,
+            repair_budget: 2,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+        let _ = planned("a", no_op_invariants()).run_hypersim(budget); // RunHypersim is reachable regardless of outcome
+    }
+
+    #[test]
+    fn converged_actions_are_all_reachable() {
+        use crate::proof::BudgetUsed;
+
+        let actions = Converged::actions();
+        assert!(actions.contains(&UiAction::Execute));
+
+        let budget = BudgetUsed {
+            max_futures: 64,
+            max_depth: 4,
+            repair_budget: 2,
+            explored_futures: 0,
+            pruned_futures: 0,
+            repairs_consumed: 0,
+        };
+        let converged = planned("a", no_reorders_invariants()).run_hypersim(budget).unwrap();
+        let _ = converged.execute(); // Execute
+    }
+
+    #[test]
+    fn terminal_states_advertise_and_reach_no_actions() {
+        assert!(Executed::actions().is_empty());
+        assert!(Halted::actions().is_empty());
+    }
+
+    #[test]
+    fn ui_contract_on_a_live_instance_matches_contract_for_state() {
+        use crate::domain::deletion::{AccountId, DeletionCOS};
+
+        let draft = DeletionCOS::new(AccountId("a".into()));
+        assert_eq!(draft.ui_contract().tag, UiStateTag::Draft);
+        assert_eq!(draft.ui_contract().allowed, contract_for_state(UiStateTag::Draft).allowed);
+    }
 }